@@ -5,8 +5,12 @@ use std::{fs::File, io::Write, ops::Range, thread::JoinHandle, time::Duration};
 
 pub type PlayerThread = JoinHandle<Result<(), PlayError>>;
 
+/// Yields `true`/`false` for every index `0..`, indicating whether that
+/// index falls in `range`. Unbounded rather than stopping at `range.end` so
+/// that zipping against a data iterator masks over the data's *full*
+/// length, not just up to the end of the selected range.
 pub fn iter_mask(range: Range<usize>) -> impl Iterator<Item = bool> {
-    (0..range.end).map(move |n| range.contains(&n))
+    (0..).map(move |n| range.contains(&n))
 }
 
 fn apply_conditional_map<I, O, F>(into_iterator: &I, range: Range<usize>, f: F) -> O
@@ -39,6 +43,33 @@ pub struct WavSettings {
     sample_rate: u32,
 }
 
+impl WavSettings {
+    pub fn new(channel_count: u16, sample_rate: u32) -> WavSettings {
+        WavSettings {
+            channel_count,
+            sample_rate,
+        }
+    }
+
+    pub fn channel_count(&self) -> u16 {
+        self.channel_count
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+/// Interpolation strategy used by [`SoundData::resample`].
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum InterpolationMode {
+    Nearest,
+    Linear,
+    Cosine,
+    Cubic,
+    Sinc,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct SoundData {
     wav_settings: WavSettings,
@@ -53,6 +84,12 @@ impl SoundData {
         }
     }
 
+    /// Appends already-interleaved samples to the end of the buffer, e.g.
+    /// for building up a `SoundData` from streamed/decoded packets.
+    pub fn push_samples(&mut self, samples: &[i16]) {
+        self.samples.extend_from_slice(samples);
+    }
+
     pub fn set_sample(&mut self, index: usize, channel: usize, new_sample: i16) -> bool {
         let index: usize = index * self.wav_settings.channel_count as usize + channel;
         let maybe_sample: Option<&mut i16> = self.samples.get_mut(index);
@@ -72,17 +109,183 @@ impl SoundData {
             .map(|x| *x as f32 / (i16::MAX as f32))
             .collect();
         let sample_rate: u32 = self.wav_settings.sample_rate;
+        let channel_count: u16 = self.wav_settings.channel_count;
 
         std::thread::spawn(move || -> Result<(), PlayError> {
             let (_stream, stream_handle) =
                 OutputStream::try_default().expect("Oops! Could not get device to play audio to!");
-            let samples_buffer: SamplesBuffer<f32> = SamplesBuffer::new(1, sample_rate, buffer);
+            let samples_buffer: SamplesBuffer<f32> = SamplesBuffer::new(channel_count, sample_rate, buffer);
             let duration: Duration = samples_buffer.total_duration().expect("no duration found");
             stream_handle.play_raw(samples_buffer)?;
             std::thread::sleep(duration);
             Ok(())
         })
     }
+
+    /// Number of interleaved channels per frame.
+    pub fn channel_count(&self) -> u16 {
+        self.wav_settings.channel_count
+    }
+
+    pub fn wav_settings(&self) -> WavSettings {
+        self.wav_settings
+    }
+
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+
+    /// Averages interleaved frames down to a single mono channel. The
+    /// average is truncated (not rounded) to `i16`, and a trailing partial
+    /// frame (when `samples.len()` isn't a multiple of `channel_count`) is
+    /// dropped.
+    pub fn to_mono(&self) -> SoundData {
+        let channel_count: usize = self.wav_settings.channel_count as usize;
+        if channel_count <= 1 {
+            return self.clone();
+        }
+
+        let samples: Vec<i16> = self
+            .samples
+            .chunks_exact(channel_count)
+            .map(|frame| {
+                let sum: i32 = frame.iter().map(|s| *s as i32).sum();
+                (sum / channel_count as i32) as i16
+            })
+            .collect();
+
+        SoundData {
+            wav_settings: WavSettings {
+                channel_count: 1,
+                sample_rate: self.wav_settings.sample_rate,
+            },
+            samples,
+        }
+    }
+
+    /// Duplicates each frame out to `channels` channels. Only defined when
+    /// converting up from mono; other channel counts are returned unchanged.
+    pub fn to_channels(&self, channels: u16) -> SoundData {
+        if self.wav_settings.channel_count != 1 || channels <= 1 {
+            return self.clone();
+        }
+
+        let mut samples: Vec<i16> = Vec::with_capacity(self.samples.len() * channels as usize);
+        for sample in &self.samples {
+            for _ in 0..channels {
+                samples.push(*sample);
+            }
+        }
+
+        SoundData {
+            wav_settings: WavSettings {
+                channel_count: channels,
+                sample_rate: self.wav_settings.sample_rate,
+            },
+            samples,
+        }
+    }
+
+    /// Resamples to `target_rate` using `mode`, walking the output timeline
+    /// at step `r_in / r_out` and interpolating each fractional source
+    /// position. Out-of-range source indices clamp by repeating the edge
+    /// sample. Updates `wav_settings.sample_rate` on the result.
+    pub fn resample(&self, target_rate: u32, mode: InterpolationMode) -> SoundData {
+        let channel_count: usize = self.wav_settings.channel_count as usize;
+        let frame_count: usize = self.samples.len() / channel_count.max(1);
+
+        if frame_count == 0 || channel_count == 0 || self.wav_settings.sample_rate == 0 {
+            return SoundData {
+                wav_settings: WavSettings {
+                    channel_count: self.wav_settings.channel_count,
+                    sample_rate: target_rate,
+                },
+                samples: self.samples.clone(),
+            };
+        }
+
+        let step: f64 = self.wav_settings.sample_rate as f64 / target_rate as f64;
+        let out_frame_count: usize =
+            ((frame_count as f64) * (target_rate as f64) / (self.wav_settings.sample_rate as f64)).round() as usize;
+
+        let frame_at = |frame: isize, channel: usize| -> f64 {
+            let clamped: usize = frame.clamp(0, frame_count as isize - 1) as usize;
+            self.samples[clamped * channel_count + channel] as f64
+        };
+
+        let mut samples: Vec<i16> = Vec::with_capacity(out_frame_count * channel_count);
+        for out_frame in 0..out_frame_count {
+            let p: f64 = out_frame as f64 * step;
+            let i: isize = p.floor() as isize;
+            let t: f64 = p - p.floor();
+
+            for channel in 0..channel_count {
+                let value: f64 = match mode {
+                    InterpolationMode::Nearest => frame_at(p.round() as isize, channel),
+                    InterpolationMode::Linear => {
+                        let s0 = frame_at(i, channel);
+                        let s1 = frame_at(i + 1, channel);
+                        s0 + t * (s1 - s0)
+                    }
+                    InterpolationMode::Cosine => {
+                        let s0 = frame_at(i, channel);
+                        let s1 = frame_at(i + 1, channel);
+                        let t2 = (1.0 - (t * std::f64::consts::PI).cos()) / 2.0;
+                        s0 + t2 * (s1 - s0)
+                    }
+                    InterpolationMode::Cubic => {
+                        let s0 = frame_at(i - 1, channel);
+                        let s1 = frame_at(i, channel);
+                        let s2 = frame_at(i + 1, channel);
+                        let s3 = frame_at(i + 2, channel);
+                        let a = -0.5 * s0 + 1.5 * s1 - 1.5 * s2 + 0.5 * s3;
+                        let b = s0 - 2.5 * s1 + 2.0 * s2 - 0.5 * s3;
+                        let c = -0.5 * s0 + 0.5 * s2;
+                        let d = s1;
+                        ((a * t + b) * t + c) * t + d
+                    }
+                    InterpolationMode::Sinc => {
+                        const TAPS: isize = 16;
+                        let mut weights: [f64; TAPS as usize] = [0.0; TAPS as usize];
+                        let mut weight_sum: f64 = 0.0;
+                        for k in -(TAPS / 2)..(TAPS / 2) {
+                            let tap_index = i + k;
+                            let x = p - tap_index as f64;
+                            let sinc = if x.abs() < 1e-9 {
+                                1.0
+                            } else {
+                                (std::f64::consts::PI * x).sin() / (std::f64::consts::PI * x)
+                            };
+                            let window = 0.5
+                                - 0.5
+                                    * (2.0 * std::f64::consts::PI * (k + TAPS / 2) as f64 / (TAPS - 1) as f64).cos();
+                            let weight = sinc * window;
+                            weights[(k + TAPS / 2) as usize] = weight;
+                            weight_sum += weight;
+                        }
+
+                        let mut acc: f64 = 0.0;
+                        for k in -(TAPS / 2)..(TAPS / 2) {
+                            let tap_index = i + k;
+                            let weight = weights[(k + TAPS / 2) as usize];
+                            acc += frame_at(tap_index, channel) * weight / weight_sum;
+                        }
+                        acc
+                    }
+                };
+
+                samples.push(value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16);
+            }
+        }
+
+        SoundData {
+            wav_settings: WavSettings {
+                channel_count: self.wav_settings.channel_count,
+                sample_rate: target_rate,
+            },
+            samples,
+        }
+    }
 }
 
 impl ConditionalMappable for SoundData {
@@ -122,10 +325,14 @@ impl WavHeader {
     pub const HEADER_SIZE: u32 = 44;
     pub const BYTES_PER_SAMPLE: u16 = 2;
 
-    pub fn new(file_size: u32, wav_settings: WavSettings) -> WavHeader {
+    /// `data_size` is the size in bytes of the sample data that will follow
+    /// the header (i.e. `sample_count * BYTES_PER_SAMPLE`), *not* a sample
+    /// count. `file_size` is the RIFF chunk size: total file size minus the
+    /// 8 bytes of the `riff`/`file_size` fields themselves.
+    pub fn new(data_size: u32, wav_settings: WavSettings) -> WavHeader {
         WavHeader {
             riff: Self::RIFF,
-            file_size,
+            file_size: Self::HEADER_SIZE - 8 + data_size,
             wave: Self::WAVE,
             fmt: Self::FMT,
             format_size: Self::FORMAT_SIZE,
@@ -138,7 +345,7 @@ impl WavHeader {
             bytes_per_chunk: Self::BYTES_PER_SAMPLE * wav_settings.channel_count,
             bits_per_sample: Self::BYTES_PER_SAMPLE * 8,
             data: Self::DATA,
-            data_size: file_size - Self::HEADER_SIZE,
+            data_size,
         }
     }
 }
@@ -151,16 +358,201 @@ pub struct WavFile {
 
 impl WavFile {
     pub fn new(sound_data: SoundData) -> WavFile {
+        let data_size: u32 = sound_data.samples.len() as u32 * WavHeader::BYTES_PER_SAMPLE as u32;
         WavFile {
-            header: WavHeader::new(sound_data.samples.len() as u32, sound_data.wav_settings),
+            header: WavHeader::new(data_size, sound_data.wav_settings),
             samples: sound_data.samples,
         }
     }
 
+    /// Writes a byte-accurate, spec-compliant RIFF/WAVE file: the 44-byte
+    /// header field-by-field as little-endian bytes, followed by each
+    /// sample as `i16::to_le_bytes`. This is the default export path.
     pub fn export(&self, file: &mut File) -> Result<(), ExportError> {
-        let bytes: Vec<u8> = serialize(self).map_err(|e| ExportError::BincodeError(e))?;
+        let mut bytes: Vec<u8> = Vec::with_capacity(WavHeader::HEADER_SIZE as usize + self.samples.len() * 2);
+
+        bytes.extend_from_slice(&self.header.riff);
+        bytes.extend_from_slice(&self.header.file_size.to_le_bytes());
+        bytes.extend_from_slice(&self.header.wave);
+        bytes.extend_from_slice(&self.header.fmt);
+        bytes.extend_from_slice(&self.header.format_size.to_le_bytes());
+        bytes.extend_from_slice(&self.header.format_type.to_le_bytes());
+        bytes.extend_from_slice(&self.header.channel_count.to_le_bytes());
+        bytes.extend_from_slice(&self.header.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.header.byte_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.header.bytes_per_chunk.to_le_bytes());
+        bytes.extend_from_slice(&self.header.bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(&self.header.data);
+        bytes.extend_from_slice(&self.header.data_size.to_le_bytes());
+
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+
         file.write_all(&bytes)
-            .map_err(|e| ExportError::FileError(e))?;
+            .map_err(ExportError::FileError)?;
         Ok(())
     }
+
+    /// Bincode-serialized fallback export. Not a spec-compliant WAV file
+    /// (bincode adds its own length prefixes and integer encoding), but kept
+    /// around for round-tripping a `WavFile` through `serde`.
+    pub fn export_bincode(&self, file: &mut File) -> Result<(), ExportError> {
+        let bytes: Vec<u8> = serialize(self).map_err(ExportError::BincodeError)?;
+        file.write_all(&bytes)
+            .map_err(ExportError::FileError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn exported_bytes(samples: &[i16], wav_settings: WavSettings, tag: &str) -> Vec<u8> {
+        let mut sound_data = SoundData::new(wav_settings);
+        sound_data.push_samples(samples);
+        let wav_file = WavFile::new(sound_data);
+
+        let path = std::env::temp_dir().join(format!("brie_daw_export_test_{tag}.wav"));
+        let mut file = File::create(&path).expect("create temp file");
+        wav_file.export(&mut file).expect("export");
+        let bytes = fs::read(&path).expect("read temp file");
+        fs::remove_file(&path).ok();
+        bytes
+    }
+
+    #[test]
+    fn export_header_sizes_match_actual_byte_lengths() {
+        let samples: Vec<i16> = vec![1, -2, 3, -4, 5];
+        let bytes = exported_bytes(&samples, WavSettings::new(1, 44_100), "short");
+
+        let expected_data_size = (samples.len() * 2) as u32;
+        let expected_file_size = WavHeader::HEADER_SIZE - 8 + expected_data_size;
+
+        assert_eq!(bytes.len() as u32, WavHeader::HEADER_SIZE + expected_data_size);
+        assert_eq!(
+            u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            expected_file_size
+        );
+        assert_eq!(
+            u32::from_le_bytes(bytes[40..44].try_into().unwrap()),
+            expected_data_size
+        );
+    }
+
+    #[test]
+    fn export_roundtrips_samples_as_little_endian_i16() {
+        let samples: Vec<i16> = vec![1000, -1000, i16::MAX, i16::MIN];
+        let bytes = exported_bytes(&samples, WavSettings::new(2, 48_000), "roundtrip");
+
+        let data = &bytes[WavHeader::HEADER_SIZE as usize..];
+        let decoded: Vec<i16> = data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn resample_with_zero_source_sample_rate_does_not_panic() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, 0));
+        sound_data.push_samples(&[0, 100, 200, 300]);
+
+        let resampled = sound_data.resample(48_000, InterpolationMode::Sinc);
+
+        assert_eq!(resampled.wav_settings().sample_rate(), 48_000);
+        assert_eq!(resampled.samples(), &[0, 100, 200, 300]);
+    }
+
+    #[test]
+    fn resample_nearest_upsamples_by_repeating_samples() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, 8_000));
+        sound_data.push_samples(&[0, 100, 200, 300]);
+
+        let resampled = sound_data.resample(16_000, InterpolationMode::Nearest);
+
+        assert_eq!(resampled.wav_settings().sample_rate(), 16_000);
+        assert_eq!(resampled.samples().len(), 8);
+    }
+
+    #[test]
+    fn resample_linear_interpolates_between_known_samples() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, 2));
+        sound_data.push_samples(&[0, 100]);
+
+        let resampled = sound_data.resample(4, InterpolationMode::Linear);
+
+        assert_eq!(resampled.samples(), &[0, 50, 100, 100]);
+    }
+
+    #[test]
+    fn resample_preserves_constant_signal_across_all_modes() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, 8_000));
+        sound_data.push_samples(&[1000i16; 64]);
+
+        for mode in [
+            InterpolationMode::Nearest,
+            InterpolationMode::Linear,
+            InterpolationMode::Cosine,
+            InterpolationMode::Cubic,
+            InterpolationMode::Sinc,
+        ] {
+            let resampled = sound_data.resample(16_000, mode);
+            for sample in resampled.samples() {
+                assert!(
+                    (*sample as i32 - 1000).abs() <= 1,
+                    "mode {mode:?} produced {sample} for a constant 1000 signal"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn to_mono_averages_interleaved_stereo_frames() {
+        let mut sound_data = SoundData::new(WavSettings::new(2, 44_100));
+        sound_data.push_samples(&[0, 10, 100, 200]);
+
+        let mono = sound_data.to_mono();
+
+        assert_eq!(mono.channel_count(), 1);
+        assert_eq!(mono.samples(), &[5, 150]);
+    }
+
+    #[test]
+    fn to_mono_drops_trailing_partial_frame() {
+        // 5 interleaved samples at channel_count=2 is one full frame short
+        // of a second frame; the trailing sample is dropped rather than
+        // averaged/padded.
+        let mut sound_data = SoundData::new(WavSettings::new(2, 44_100));
+        sound_data.push_samples(&[0, 10, 100, 200, 999]);
+
+        let mono = sound_data.to_mono();
+
+        assert_eq!(mono.samples(), &[5, 150]);
+    }
+
+    #[test]
+    fn to_mono_truncates_rather_than_rounds_the_average() {
+        let mut sound_data = SoundData::new(WavSettings::new(2, 44_100));
+        sound_data.push_samples(&[1, 2]);
+
+        let mono = sound_data.to_mono();
+
+        // (1 + 2) / 2 == 1 under integer division, not 2 as a rounded
+        // average would give.
+        assert_eq!(mono.samples(), &[1]);
+    }
+
+    #[test]
+    fn to_channels_duplicates_mono_frames() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, 44_100));
+        sound_data.push_samples(&[10, 20]);
+
+        let stereo = sound_data.to_channels(2);
+
+        assert_eq!(stereo.channel_count(), 2);
+        assert_eq!(stereo.samples(), &[10, 10, 20, 20]);
+    }
 }
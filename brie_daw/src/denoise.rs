@@ -0,0 +1,312 @@
+use crate::audio::{InterpolationMode, SoundData};
+use rustfft::{num_complex::Complex, FftPlanner};
+use std::collections::VecDeque;
+
+/// Frame size and target rate RNNoise-style suppression operates at;
+/// anything else gets resampled up front via the windowed-sinc resampler.
+const FRAME_SIZE: usize = 480;
+const HOP_SIZE: usize = FRAME_SIZE / 2;
+const TARGET_SAMPLE_RATE: u32 = 48_000;
+
+/// Number of Bark-like bands the spectrum is bucketed into for gain
+/// estimation.
+const NUM_BANDS: usize = 18;
+
+/// How many trailing frames the running noise floor is tracked over.
+const NOISE_FLOOR_FRAMES: usize = 20;
+
+pub trait Denoisable {
+    /// Runs frame-based spectral noise suppression at full (1.0) strength.
+    fn denoise(&mut self) -> Self;
+
+    /// Same as `denoise`, but `strength` in `[0.0, 1.0]` scales how far the
+    /// estimated per-band gain is allowed to attenuate (0 = untouched,
+    /// 1 = full suppression of estimated noise). Internally resamples to
+    /// `TARGET_SAMPLE_RATE` for processing and back to the original rate
+    /// afterwards, so the result keeps the input's original sample rate.
+    fn denoise_with_strength(&mut self, strength: f32) -> Self;
+}
+
+impl Denoisable for SoundData {
+    fn denoise(&mut self) -> Self {
+        self.denoise_with_strength(1.0)
+    }
+
+    fn denoise_with_strength(&mut self, strength: f32) -> Self {
+        let strength: f32 = strength.clamp(0.0, 1.0);
+        let original_sample_rate: u32 = self.wav_settings().sample_rate();
+        let source: SoundData = if original_sample_rate == TARGET_SAMPLE_RATE {
+            self.clone()
+        } else {
+            self.resample(TARGET_SAMPLE_RATE, InterpolationMode::Sinc)
+        };
+
+        let channel_count: usize = source.channel_count().max(1) as usize;
+        let samples: &[i16] = source.samples();
+        let frame_count: usize = samples.len() / channel_count;
+
+        let mut channels: Vec<Vec<f32>> = vec![Vec::with_capacity(frame_count); channel_count];
+        for (i, sample) in samples.iter().enumerate() {
+            channels[i % channel_count].push(*sample as f32);
+        }
+
+        let denoised_channels: Vec<Vec<f32>> = channels
+            .into_iter()
+            .map(|channel| denoise_channel(&channel, strength))
+            .collect();
+
+        let mut result = SoundData::new(source.wav_settings());
+        let mut interleaved: Vec<i16> = Vec::with_capacity(frame_count * channel_count);
+        for frame in 0..frame_count {
+            for channel in &denoised_channels {
+                let value: f32 = *channel.get(frame).unwrap_or(&0.0);
+                interleaved.push(value.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16);
+            }
+        }
+        result.push_samples(&interleaved);
+
+        if original_sample_rate == TARGET_SAMPLE_RATE {
+            result
+        } else {
+            result.resample(original_sample_rate, InterpolationMode::Sinc)
+        }
+    }
+}
+
+fn hann_window(size: usize) -> Vec<f32> {
+    (0..size)
+        .map(|n| {
+            0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / (size as f32 - 1.0)).cos()
+        })
+        .collect()
+}
+
+fn bark_scale(freq: f32) -> f32 {
+    13.0 * (0.00076 * freq).atan() + 3.5 * (freq / 7500.0).powi(2).atan()
+}
+
+fn band_for_bin(bin: usize, max_bark: f32) -> usize {
+    // Fold bins above Nyquist back onto their conjugate mirror (`bin`,
+    // `FRAME_SIZE - bin`) so both halves of a conjugate pair land in the
+    // same band and get the same gain; otherwise the gained spectrum loses
+    // conjugate symmetry and the inverse FFT picks up a spurious imaginary
+    // part that gets silently dropped.
+    let folded_bin: usize = bin.min(FRAME_SIZE - bin);
+    let freq: f32 = folded_bin as f32 * TARGET_SAMPLE_RATE as f32 / FRAME_SIZE as f32;
+    let bark: f32 = bark_scale(freq);
+    ((bark / max_bark) * (NUM_BANDS as f32 - 1.0))
+        .round()
+        .clamp(0.0, NUM_BANDS as f32 - 1.0) as usize
+}
+
+fn denoise_channel(channel: &[f32], strength: f32) -> Vec<f32> {
+    if channel.len() < FRAME_SIZE {
+        return channel.to_vec();
+    }
+
+    let window: Vec<f32> = hann_window(FRAME_SIZE);
+    let mut planner = FftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(FRAME_SIZE);
+    let ifft = planner.plan_fft_inverse(FRAME_SIZE);
+
+    let max_bark: f32 = bark_scale(TARGET_SAMPLE_RATE as f32 / 2.0);
+    let bin_band: Vec<usize> = (0..FRAME_SIZE).map(|bin| band_for_bin(bin, max_bark)).collect();
+    let mut noise_floor_history: Vec<VecDeque<f32>> = vec![VecDeque::new(); NUM_BANDS];
+
+    let mut output: Vec<f32> = vec![0.0; channel.len()];
+    let mut normalization: Vec<f32> = vec![0.0; channel.len()];
+
+    let mut frame_start: usize = 0;
+    while frame_start + FRAME_SIZE <= channel.len() {
+        let mut spectrum: Vec<Complex<f32>> = channel[frame_start..frame_start + FRAME_SIZE]
+            .iter()
+            .zip(&window)
+            .map(|(sample, w)| Complex::new(sample * w, 0.0))
+            .collect();
+        fft.process(&mut spectrum);
+
+        let mut band_energy: Vec<f32> = vec![0.0; NUM_BANDS];
+        for (bin, value) in spectrum.iter().enumerate() {
+            band_energy[bin_band[bin]] += value.norm_sqr();
+        }
+
+        let mut band_gain: Vec<f32> = vec![1.0; NUM_BANDS];
+        for band in 0..NUM_BANDS {
+            let history = &mut noise_floor_history[band];
+            let energy = band_energy[band];
+            let noise_floor: f32 = history.iter().cloned().fold(energy, f32::min);
+
+            let estimated_gain: f32 = (energy / (energy + noise_floor + f32::EPSILON)).clamp(0.0, 1.0);
+            band_gain[band] = 1.0 - strength * (1.0 - estimated_gain);
+
+            history.push_back(energy);
+            if history.len() > NOISE_FLOOR_FRAMES {
+                history.pop_front();
+            }
+        }
+
+        for (bin, value) in spectrum.iter_mut().enumerate() {
+            *value *= band_gain[bin_band[bin]];
+        }
+
+        ifft.process(&mut spectrum);
+        let scale: f32 = 1.0 / FRAME_SIZE as f32;
+        for (i, value) in spectrum.iter().enumerate() {
+            output[frame_start + i] += value.re * scale * window[i];
+            normalization[frame_start + i] += window[i] * window[i];
+        }
+
+        frame_start += HOP_SIZE;
+    }
+
+    for ((sample, norm), original) in output.iter_mut().zip(&normalization).zip(channel) {
+        if *norm > f32::EPSILON {
+            *sample /= norm;
+        } else {
+            // Tail shorter than one hop never gets covered by the OLA
+            // loop above; pass it through untouched rather than leaving
+            // it at its zero-initialized value.
+            *sample = *original;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WavSettings;
+
+    const SEGMENT_FRAMES: usize = 10;
+    const SEGMENT_LEN: usize = FRAME_SIZE * SEGMENT_FRAMES;
+
+    fn sine_tone(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| {
+                amplitude * (2.0 * std::f32::consts::PI * freq * n as f32 / TARGET_SAMPLE_RATE as f32).sin()
+            })
+            .collect()
+    }
+
+    /// Small deterministic xorshift PRNG so noise generation doesn't need an
+    /// external `rand` dependency.
+    fn white_noise(amplitude: f32, len: usize, seed: u32) -> Vec<f32> {
+        let mut state: u32 = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let unit: f32 = (state as f32 / u32::MAX as f32) * 2.0 - 1.0;
+                unit * amplitude
+            })
+            .collect()
+    }
+
+    fn to_samples(signal: &[f32]) -> Vec<i16> {
+        signal
+            .iter()
+            .map(|s| s.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+            .collect()
+    }
+
+    fn rms(samples: &[i16]) -> f32 {
+        let sum_sq: f32 = samples.iter().map(|s| (*s as f32).powi(2)).sum();
+        (sum_sq / samples.len() as f32).sqrt()
+    }
+
+    fn rms_diff(a: &[i16], b: &[i16]) -> f32 {
+        let sum_sq: f32 = a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| (*x as f32 - *y as f32).powi(2))
+            .sum();
+        (sum_sq / a.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn denoise_suppresses_quiet_background_but_preserves_a_loud_tone() {
+        // A quiet white-noise-only segment (establishing a low noise floor
+        // per band) followed by a loud tone riding on top of the same
+        // background noise: the loud segment's band energy sits far above
+        // the floor the first segment established, so the estimated gain
+        // there is close to 1 (pass through), while the quiet segment's
+        // energy tracks close to its own floor and gets suppressed.
+        let quiet: Vec<f32> = white_noise(200.0, SEGMENT_LEN, 111);
+        let tone: Vec<f32> = sine_tone(440.0, 8000.0, SEGMENT_LEN);
+        let tail_noise: Vec<f32> = white_noise(200.0, SEGMENT_LEN, 222);
+        let loud: Vec<f32> = tone.iter().zip(&tail_noise).map(|(t, n)| t + n).collect();
+
+        let mut signal: Vec<f32> = Vec::with_capacity(SEGMENT_LEN * 2);
+        signal.extend(&quiet);
+        signal.extend(&loud);
+        let samples: Vec<i16> = to_samples(&signal);
+
+        let mut sound_data = SoundData::new(WavSettings::new(1, TARGET_SAMPLE_RATE));
+        sound_data.push_samples(&samples);
+
+        let denoised = sound_data.denoise();
+        let denoised_samples: &[i16] = denoised.samples();
+
+        let quiet_in_rms = rms(&samples[..SEGMENT_LEN]);
+        let quiet_out_rms = rms(&denoised_samples[..SEGMENT_LEN]);
+        let loud_in_rms = rms(&samples[SEGMENT_LEN..]);
+        let loud_out_rms = rms(&denoised_samples[SEGMENT_LEN..]);
+
+        assert!(
+            quiet_out_rms < quiet_in_rms * 0.85,
+            "quiet background should be suppressed: in={quiet_in_rms}, out={quiet_out_rms}"
+        );
+        assert!(
+            (loud_out_rms - loud_in_rms).abs() < loud_in_rms * 0.1,
+            "loud tone should pass through close to unchanged: in={loud_in_rms}, out={loud_out_rms}"
+        );
+    }
+
+    #[test]
+    fn denoise_with_zero_strength_passes_through_unchanged() {
+        let tone: Vec<f32> = sine_tone(440.0, 8000.0, SEGMENT_LEN);
+        let noise: Vec<f32> = white_noise(1500.0, SEGMENT_LEN, 6789);
+        let noisy_samples: Vec<i16> =
+            to_samples(&tone.iter().zip(&noise).map(|(t, n)| t + n).collect::<Vec<f32>>());
+
+        let mut sound_data = SoundData::new(WavSettings::new(1, TARGET_SAMPLE_RATE));
+        sound_data.push_samples(&noisy_samples);
+
+        let passthrough = sound_data.denoise_with_strength(0.0);
+
+        let diff: f32 = rms_diff(passthrough.samples(), &noisy_samples);
+        let input_rms: f32 = rms(&noisy_samples);
+        assert!(
+            diff < input_rms * 0.05,
+            "strength=0.0 should reconstruct the input, got rms diff {diff} against input rms {input_rms}"
+        );
+    }
+
+    #[test]
+    fn denoise_preserves_the_original_sample_rate() {
+        let tone: Vec<f32> = sine_tone(440.0, 8000.0, SEGMENT_LEN);
+        let noise: Vec<f32> = white_noise(1500.0, SEGMENT_LEN, 321);
+        let samples: Vec<i16> =
+            to_samples(&tone.iter().zip(&noise).map(|(t, n)| t + n).collect::<Vec<f32>>());
+
+        let mut sound_data = SoundData::new(WavSettings::new(1, 44_100));
+        sound_data.push_samples(&samples);
+
+        let denoised = sound_data.denoise();
+
+        assert_eq!(denoised.wav_settings().sample_rate(), 44_100);
+    }
+
+    #[test]
+    fn denoise_leaves_silence_silent() {
+        let mut sound_data = SoundData::new(WavSettings::new(1, TARGET_SAMPLE_RATE));
+        sound_data.push_samples(&vec![0i16; SEGMENT_LEN]);
+
+        let denoised = sound_data.denoise();
+
+        for sample in denoised.samples() {
+            assert_eq!(*sample, 0, "silence in should stay silence out");
+        }
+    }
+}
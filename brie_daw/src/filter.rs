@@ -0,0 +1,213 @@
+use crate::audio::{iter_mask, SoundData};
+use std::f32::consts::PI;
+use std::ops::Range;
+
+/// Extends the `ConditionalMappable` idea from sample-wise `Fn(i16) -> i16`
+/// maps to convolving a FIR kernel across a selected region.
+pub trait FirFilterable {
+    fn fir_filter(&mut self, range: Range<usize>, taps: &[f32]) -> Self;
+}
+
+impl FirFilterable for SoundData {
+    fn fir_filter(&mut self, range: Range<usize>, taps: &[f32]) -> Self {
+        let channel_count: usize = self.channel_count().max(1) as usize;
+        let samples: &[i16] = self.samples();
+        let frame_count: usize = samples.len() / channel_count;
+        let center: usize = taps.len().saturating_sub(1) / 2;
+
+        let convolved: Vec<i16> = samples
+            .iter()
+            .enumerate()
+            .zip(iter_mask(range))
+            .map(|((index, sample), in_range)| {
+                if !in_range {
+                    return *sample;
+                }
+
+                let frame: usize = index / channel_count;
+                let channel: usize = index % channel_count;
+                let mut acc: f32 = 0.0;
+                for (k, tap) in taps.iter().enumerate() {
+                    let offset: isize = k as isize - center as isize;
+                    let src_frame: usize =
+                        (frame as isize + offset).clamp(0, frame_count as isize - 1) as usize;
+                    acc += tap * samples[src_frame * channel_count + channel] as f32;
+                }
+                acc.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+            })
+            .collect();
+
+        let mut result = SoundData::new(self.wav_settings());
+        result.push_samples(&convolved);
+        result
+    }
+}
+
+/// Designs a windowed-sinc low-pass kernel for normalized cutoff `fc`
+/// (as a fraction of the sample rate) over `num_taps` taps: Hann-windowed
+/// and normalized to unit DC gain.
+pub fn low_pass_kernel(fc: f32, num_taps: usize) -> Vec<f32> {
+    if num_taps == 0 {
+        return Vec::new();
+    }
+    if num_taps == 1 {
+        // The Hann window's center term is undefined for a single tap;
+        // a lone tap can only pass everything through at unit gain.
+        return vec![1.0];
+    }
+
+    let m: f32 = num_taps as f32 - 1.0;
+
+    let mut kernel: Vec<f32> = (0..num_taps)
+        .map(|n| {
+            let n: f32 = n as f32;
+            let x: f32 = 2.0 * fc * (n - m / 2.0);
+            let sinc: f32 = if x.abs() < 1e-9 {
+                1.0
+            } else {
+                (PI * x).sin() / (PI * x)
+            };
+            let window: f32 = 0.5 - 0.5 * (2.0 * PI * n / m).cos();
+            2.0 * fc * sinc * window
+        })
+        .collect();
+
+    let dc_gain: f32 = kernel.iter().sum();
+    if dc_gain.abs() > 1e-9 {
+        for tap in &mut kernel {
+            *tap /= dc_gain;
+        }
+    }
+    kernel
+}
+
+/// Designs a high-pass kernel by spectral inversion of the matching
+/// low-pass kernel.
+pub fn high_pass_kernel(fc: f32, num_taps: usize) -> Vec<f32> {
+    let mut kernel: Vec<f32> = low_pass_kernel(fc, num_taps);
+    for tap in &mut kernel {
+        *tap = -*tap;
+    }
+
+    let center: usize = num_taps.saturating_sub(1) / 2;
+    if let Some(center_tap) = kernel.get_mut(center) {
+        *center_tap += 1.0;
+    }
+    kernel
+}
+
+/// Designs a band-pass kernel by convolving a low-pass kernel at
+/// `high_cutoff` with a high-pass kernel at `low_cutoff`. Convolving two
+/// `num_taps`-length kernels produces `2 * num_taps - 1` taps, so the result
+/// is cropped back down to `num_taps`, taken from the center, to match the
+/// tap count callers asked for (and what `low_pass_kernel`/`high_pass_kernel`
+/// return).
+pub fn band_pass_kernel(low_cutoff: f32, high_cutoff: f32, num_taps: usize) -> Vec<f32> {
+    let low: Vec<f32> = low_pass_kernel(high_cutoff, num_taps);
+    let high: Vec<f32> = high_pass_kernel(low_cutoff, num_taps);
+    let full: Vec<f32> = convolve_kernels(&low, &high);
+
+    let start: usize = (full.len() - num_taps) / 2;
+    full[start..start + num_taps].to_vec()
+}
+
+fn convolve_kernels(a: &[f32], b: &[f32]) -> Vec<f32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out: Vec<f32> = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WavSettings;
+
+    #[test]
+    fn low_pass_kernel_has_unit_dc_gain() {
+        let kernel = low_pass_kernel(0.1, 31);
+        let dc_gain: f32 = kernel.iter().sum();
+        assert!((dc_gain - 1.0).abs() < 1e-4, "dc_gain = {dc_gain}");
+    }
+
+    #[test]
+    fn high_pass_kernel_blocks_dc() {
+        let kernel = high_pass_kernel(0.1, 31);
+        let dc_gain: f32 = kernel.iter().sum();
+        assert!(dc_gain.abs() < 1e-4, "dc_gain = {dc_gain}");
+    }
+
+    #[test]
+    fn band_pass_kernel_has_requested_tap_count() {
+        let kernel = band_pass_kernel(0.1, 0.3, 31);
+        assert_eq!(kernel.len(), 31);
+    }
+
+    #[test]
+    fn band_pass_kernel_with_zero_taps_returns_empty_instead_of_panicking() {
+        let kernel = band_pass_kernel(0.1, 0.3, 0);
+        assert!(kernel.is_empty());
+    }
+
+    #[test]
+    fn low_pass_kernel_with_one_tap_is_unit_gain_passthrough() {
+        let kernel = low_pass_kernel(0.1, 1);
+        assert_eq!(kernel, vec![1.0]);
+    }
+
+    #[test]
+    fn band_pass_kernel_blocks_dc() {
+        // Convolving a low-pass (unity DC gain) with a high-pass (zero DC
+        // gain) response should leave DC blocked, same as the high-pass
+        // stage alone.
+        let kernel = band_pass_kernel(0.1, 0.3, 31);
+        let dc_gain: f32 = kernel.iter().sum();
+        assert!(dc_gain.abs() < 0.05, "dc_gain = {dc_gain}");
+    }
+
+    #[test]
+    fn fir_filter_passes_dc_signal_through_low_pass() {
+        let kernel = low_pass_kernel(0.2, 31);
+        let mut sound_data = SoundData::new(WavSettings::new(1, 8_000));
+        sound_data.push_samples(&[1000i16; 64]);
+
+        let filtered = sound_data.fir_filter(0..64, &kernel);
+
+        for sample in filtered.samples() {
+            assert!(
+                (*sample as i32 - 1000).abs() <= 1,
+                "low-pass kernel should pass a constant (DC) signal through unchanged, got {sample}"
+            );
+        }
+    }
+
+    #[test]
+    fn fir_filter_only_touches_samples_in_range() {
+        // A two-tap averaging kernel so in-range samples visibly change,
+        // letting this test tell "left alone" apart from "coincidentally
+        // unchanged" the way an identity `[1.0]` tap could not.
+        let taps = [0.5_f32, 0.5_f32];
+        let mut sound_data = SoundData::new(WavSettings::new(1, 8_000));
+        sound_data.push_samples(&[10, 20, 30, 40]);
+
+        let filtered = sound_data.fir_filter(1..3, &taps);
+
+        // Samples 0 and 3 are outside the range and must pass through
+        // untouched; the full 4-element buffer must come back, not get
+        // truncated to the range.
+        assert_eq!(filtered.samples()[0], 10);
+        assert_eq!(filtered.samples()[3], 40);
+        assert_eq!(filtered.samples().len(), 4);
+        // Samples 1 and 2 are inside the range and must have actually been
+        // convolved with the kernel.
+        assert_ne!(filtered.samples()[1], 20);
+        assert_ne!(filtered.samples()[2], 30);
+    }
+}
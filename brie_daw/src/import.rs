@@ -0,0 +1,348 @@
+use crate::audio::{PlayerThread, SoundData, WavSettings};
+use rodio::{OutputStream, PlayError, Sink, Source};
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    Symphonia(SymphoniaError),
+    NoSupportedTrack,
+    Play(PlayError),
+}
+
+/// Probes `path` with Symphonia and decodes the first supported track (MP3,
+/// OGG, FLAC, WAV, ...) fully into an in-memory `SoundData`.
+pub fn import(path: &Path) -> Result<SoundData, ImportError> {
+    let (format, track_id, decoder, wav_settings) = open_track(path)?;
+    decode_all(format, track_id, decoder, wav_settings)
+}
+
+/// Drains `format` of every packet belonging to `track_id`, decoding each
+/// through `decoder` into `wav_settings`-shaped `SoundData`. A single
+/// undecodeable packet (`DecodeError`) is skipped rather than aborting the
+/// whole import; an `IoError` from `next_packet` is treated as end-of-stream.
+fn decode_all(
+    mut format: Box<dyn FormatReader>,
+    track_id: u32,
+    mut decoder: Box<dyn Decoder>,
+    wav_settings: WavSettings,
+) -> Result<SoundData, ImportError> {
+    let mut sound_data = SoundData::new(wav_settings);
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(_)) => break,
+            Err(e) => return Err(ImportError::Symphonia(e)),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut sample_buf = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+                sample_buf.copy_interleaved_ref(decoded);
+                sound_data.push_samples(sample_buf.samples());
+            }
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(ImportError::Symphonia(e)),
+        }
+    }
+
+    Ok(sound_data)
+}
+
+/// Decodes `path` on the player thread and feeds packets to rodio
+/// incrementally, rather than materializing the whole file first.
+pub fn play_streaming(path: &Path) -> Result<PlayerThread, ImportError> {
+    let (format, track_id, decoder, wav_settings) = open_track(path)?;
+    let source = StreamingSource {
+        format,
+        decoder,
+        track_id,
+        sample_buf: None,
+        buf_pos: 0,
+        channels: wav_settings.channel_count(),
+        sample_rate: wav_settings.sample_rate(),
+    };
+
+    Ok(std::thread::spawn(move || -> Result<(), PlayError> {
+        let (_stream, stream_handle) =
+            OutputStream::try_default().expect("Oops! Could not get device to play audio to!");
+        let sink = Sink::try_new(&stream_handle)?;
+        sink.append(source);
+        sink.sleep_until_end();
+        Ok(())
+    }))
+}
+
+/// Format reader, the id of the track selected from it, a matching
+/// decoder, and the `WavSettings` derived from that track's codec params.
+type OpenedTrack = (Box<dyn FormatReader>, u32, Box<dyn Decoder>, WavSettings);
+
+fn open_track(path: &Path) -> Result<OpenedTrack, ImportError> {
+    let file = std::fs::File::open(path).map_err(ImportError::Io)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(ImportError::Symphonia)?;
+    let format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(ImportError::NoSupportedTrack)?;
+    let track_id = track.id;
+    let channel_count = track
+        .codec_params
+        .channels
+        .map(|c| c.count())
+        .unwrap_or(1) as u16;
+    let sample_rate = track.codec_params.sample_rate.unwrap_or(44_100);
+
+    let decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(ImportError::Symphonia)?;
+
+    Ok((
+        format,
+        track_id,
+        decoder,
+        WavSettings::new(channel_count, sample_rate),
+    ))
+}
+
+impl From<PlayError> for ImportError {
+    fn from(e: PlayError) -> Self {
+        ImportError::Play(e)
+    }
+}
+
+/// A rodio `Source` that pulls decoded samples from a Symphonia format
+/// reader + decoder pair on demand, one packet at a time.
+struct StreamingSource {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_buf: Option<SampleBuffer<f32>>,
+    buf_pos: usize,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl Iterator for StreamingSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        loop {
+            if let Some(buf) = &self.sample_buf {
+                if self.buf_pos < buf.samples().len() {
+                    let sample = buf.samples()[self.buf_pos];
+                    self.buf_pos += 1;
+                    return Some(sample);
+                }
+            }
+
+            let packet = loop {
+                match self.format.next_packet() {
+                    Ok(packet) if packet.track_id() == self.track_id => break packet,
+                    Ok(_) => continue,
+                    Err(_) => return None,
+                }
+            };
+
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    let spec = *decoded.spec();
+                    let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                    buf.copy_interleaved_ref(decoded);
+                    self.sample_buf = Some(buf);
+                    self.buf_pos = 0;
+                }
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl Source for StreamingSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{WavFile, WavSettings};
+    use std::fs;
+    use symphonia::core::audio::AudioBufferRef;
+    use symphonia::core::codecs::{CodecParameters, DecoderOptions, FinalizeResult};
+    use symphonia::core::errors::Result as SymphoniaResult;
+    use symphonia::core::formats::{Cue, SeekMode, SeekTo, SeekedTo, Track};
+    use symphonia::core::meta::{Metadata, MetadataLog};
+
+    #[test]
+    fn import_round_trips_a_small_generated_wav() {
+        let samples: Vec<i16> = vec![0, 1000, -1000, 3000, -3000, 100];
+        let wav_settings = WavSettings::new(1, 44_100);
+        let mut sound_data = SoundData::new(wav_settings);
+        sound_data.push_samples(&samples);
+        let wav_file = WavFile::new(sound_data);
+
+        let path = std::env::temp_dir().join("brie_daw_import_test_roundtrip.wav");
+        let mut file = std::fs::File::create(&path).expect("create temp file");
+        wav_file.export(&mut file).expect("export");
+
+        let imported = import(&path).expect("import");
+        fs::remove_file(&path).ok();
+
+        assert_eq!(imported.wav_settings().channel_count(), 1);
+        assert_eq!(imported.wav_settings().sample_rate(), 44_100);
+        assert_eq!(imported.samples(), samples.as_slice());
+    }
+
+    /// A `Decoder` stub that replays a fixed sequence of `decode` results,
+    /// so a single corrupt packet can be simulated without a real codec.
+    struct ScriptedDecoder {
+        results: std::collections::VecDeque<SymphoniaError>,
+        params: CodecParameters,
+    }
+
+    impl Decoder for ScriptedDecoder {
+        fn try_new(_params: &CodecParameters, _options: &DecoderOptions) -> SymphoniaResult<Self> {
+            unimplemented!("not exercised: tests construct ScriptedDecoder directly")
+        }
+
+        fn supported_codecs() -> &'static [symphonia::core::codecs::CodecDescriptor] {
+            &[]
+        }
+
+        fn reset(&mut self) {}
+
+        fn codec_params(&self) -> &CodecParameters {
+            &self.params
+        }
+
+        fn decode(
+            &mut self,
+            _packet: &symphonia::core::formats::Packet,
+        ) -> SymphoniaResult<AudioBufferRef<'_>> {
+            Err(self
+                .results
+                .pop_front()
+                .expect("no more scripted decode results"))
+        }
+
+        fn finalize(&mut self) -> FinalizeResult {
+            FinalizeResult::default()
+        }
+
+        fn last_decoded(&self) -> AudioBufferRef<'_> {
+            unimplemented!("not exercised: decode_all never reads the last-decoded buffer")
+        }
+    }
+
+    /// A `FormatReader` stub that yields a fixed sequence of packets for a
+    /// single track, then end-of-stream, without needing a real container.
+    struct ScriptedFormatReader {
+        packets: std::collections::VecDeque<symphonia::core::formats::Packet>,
+        meta_log: MetadataLog,
+    }
+
+    impl FormatReader for ScriptedFormatReader {
+        fn try_new(
+            _source: symphonia::core::io::MediaSourceStream,
+            _options: &symphonia::core::formats::FormatOptions,
+        ) -> SymphoniaResult<Self> {
+            unimplemented!("not exercised: tests construct ScriptedFormatReader directly")
+        }
+
+        fn cues(&self) -> &[Cue] {
+            &[]
+        }
+
+        fn metadata(&mut self) -> Metadata<'_> {
+            self.meta_log.metadata()
+        }
+
+        fn seek(&mut self, _mode: SeekMode, _to: SeekTo) -> SymphoniaResult<SeekedTo> {
+            unimplemented!("not exercised: decode_all never seeks")
+        }
+
+        fn tracks(&self) -> &[Track] {
+            &[]
+        }
+
+        fn next_packet(&mut self) -> SymphoniaResult<symphonia::core::formats::Packet> {
+            self.packets
+                .pop_front()
+                .ok_or(SymphoniaError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "end of stream",
+                )))
+        }
+
+        fn into_inner(self: Box<Self>) -> symphonia::core::io::MediaSourceStream {
+            unimplemented!("not exercised: decode_all never reclaims the source stream")
+        }
+    }
+
+    #[test]
+    fn import_skips_a_corrupt_packet_instead_of_failing_the_whole_import() {
+        const TRACK_ID: u32 = 0;
+
+        let format = Box::new(ScriptedFormatReader {
+            packets: std::collections::VecDeque::from([
+                symphonia::core::formats::Packet::new_from_slice(TRACK_ID, 0, 0, &[0u8; 4]),
+            ]),
+            meta_log: MetadataLog::default(),
+        });
+        let decoder = Box::new(ScriptedDecoder {
+            results: std::collections::VecDeque::from([SymphoniaError::DecodeError(
+                "corrupt frame",
+            )]),
+            params: CodecParameters::default(),
+        });
+
+        let result = decode_all(format, TRACK_ID, decoder, WavSettings::new(1, 44_100));
+
+        let sound_data = result.expect("a corrupt packet should be skipped, not fail the import");
+        assert!(sound_data.samples().is_empty());
+    }
+}
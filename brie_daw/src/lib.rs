@@ -0,0 +1,4 @@
+pub mod audio;
+pub mod denoise;
+pub mod filter;
+pub mod import;